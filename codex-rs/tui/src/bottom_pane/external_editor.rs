@@ -92,19 +92,26 @@ impl ExternalEditorError {
     }
 }
 
+/// Default temp-file extension. Composer content is typically Markdown, so this
+/// lets `$EDITOR`/`$VISUAL` pick up Markdown highlighting and wrapping.
+const DEFAULT_EXTENSION: &str = ".md";
+
 pub(crate) fn launch_external_editor(
     initial_text: &str,
     override_command: &Option<Vec<String>>,
+    extension: Option<&str>,
 ) -> Result<String, ExternalEditorError> {
+    let suffix = normalize_extension(extension)?;
     let editor_command = resolve_editor_command(override_command)?;
-    let (temp_path, path_buf) = create_temp_file(initial_text)?;
+    let (temp_path, path_buf) = create_temp_file(initial_text, &suffix)?;
 
     let mut terminal_guard = TerminalModeGuard::new();
     if let Some(error) = terminal_guard.take_restore_error() {
         return Err(ExternalEditorError::TerminalRestore(error));
     }
 
-    run_editor(&editor_command, &path_buf)?;
+    let (line, col) = end_of_text_position(initial_text);
+    run_editor(&editor_command, &path_buf, line, col)?;
 
     // Re-enable TUI modes immediately after the editor closes.
     drop(terminal_guard);
@@ -166,10 +173,34 @@ fn default_editor_command() -> Option<Vec<String>> {
     Some(vec!["nano".to_string()])
 }
 
-fn create_temp_file(initial_text: &str) -> Result<(TempPath, PathBuf), ExternalEditorError> {
+/// Validate a caller-supplied extension hint and resolve it to a `Builder::suffix`
+/// value (always including the leading dot). Falls back to [`DEFAULT_EXTENSION`]
+/// when no hint is given; rejects empty hints or ones containing path separators.
+fn normalize_extension(extension: Option<&str>) -> Result<String, ExternalEditorError> {
+    let Some(hint) = extension else {
+        return Ok(DEFAULT_EXTENSION.to_string());
+    };
+    let trimmed = hint.trim_start_matches('.');
+    if trimmed.is_empty() {
+        return Err(ExternalEditorError::Extension(format!(
+            "editor file extension \"{hint}\" is empty"
+        )));
+    }
+    if trimmed.contains(['/', '\\']) {
+        return Err(ExternalEditorError::Extension(format!(
+            "editor file extension \"{hint}\" must not contain path separators"
+        )));
+    }
+    Ok(format!(".{trimmed}"))
+}
+
+fn create_temp_file(
+    initial_text: &str,
+    suffix: &str,
+) -> Result<(TempPath, PathBuf), ExternalEditorError> {
     let mut file = Builder::new()
         .prefix("codex-compose-")
-        .suffix(".txt")
+        .suffix(suffix)
         .tempfile()
         .map_err(ExternalEditorError::TempFileCreate)?;
     let path = file.path().to_path_buf();
@@ -190,10 +221,15 @@ fn create_temp_file(initial_text: &str) -> Result<(TempPath, PathBuf), ExternalE
     Ok((temp_path, path))
 }
 
-fn run_editor(command: &[String], path: &PathBuf) -> Result<(), ExternalEditorError> {
-    let status = Command::new(&command[0])
-        .args(&command[1..])
-        .arg(path)
+fn run_editor(
+    command: &[String],
+    path: &PathBuf,
+    line: usize,
+    col: usize,
+) -> Result<(), ExternalEditorError> {
+    let argv = build_invocation(command, path, line, col);
+    let status = Command::new(&argv[0])
+        .args(&argv[1..])
         .status()
         .map_err(|error| ExternalEditorError::EditorLaunch {
             command: join_command(command),
@@ -211,6 +247,92 @@ fn run_editor(command: &[String], path: &PathBuf) -> Result<(), ExternalEditorEr
     Ok(())
 }
 
+/// Resolve the full argument vector used to launch the editor at `line`/`col`.
+///
+/// If the command contains a `{file}`, `{line}`, or `{col}` placeholder, those
+/// tokens are substituted in place and the path is *not* appended. Otherwise a
+/// few well-known editors get their native jump-to-line form injected, and
+/// everything else falls back to appending the path as the final argument.
+fn build_invocation(command: &[String], path: &PathBuf, line: usize, col: usize) -> Vec<String> {
+    let path_str = path.to_string_lossy().into_owned();
+
+    if command.iter().any(|arg| contains_placeholder(arg)) {
+        return command
+            .iter()
+            .map(|arg| {
+                arg.replace("{file}", &path_str)
+                    .replace("{line}", &line.to_string())
+                    .replace("{col}", &col.to_string())
+            })
+            .collect();
+    }
+
+    let mut argv = command.to_vec();
+    match editor_kind(&command[0]) {
+        EditorKind::Vim => {
+            argv.push(format!("+{line}"));
+            argv.push(path_str);
+        }
+        EditorKind::Nano => {
+            argv.push(format!("+{line},{col}"));
+            argv.push(path_str);
+        }
+        EditorKind::Emacs => {
+            argv.push(format!("+{line}:{col}"));
+            argv.push(path_str);
+        }
+        EditorKind::Helix => {
+            argv.push(format!("{path_str}:{line}:{col}"));
+        }
+        EditorKind::VsCode => {
+            argv.push("--goto".to_string());
+            argv.push(format!("{path_str}:{line}:{col}"));
+        }
+        EditorKind::Other => {
+            argv.push(path_str);
+        }
+    }
+    argv
+}
+
+fn contains_placeholder(arg: &str) -> bool {
+    arg.contains("{file}") || arg.contains("{line}") || arg.contains("{col}")
+}
+
+/// Known editors whose jump-to-line invocation we can synthesize automatically.
+enum EditorKind {
+    Vim,
+    Nano,
+    Emacs,
+    Helix,
+    VsCode,
+    Other,
+}
+
+fn editor_kind(program: &str) -> EditorKind {
+    let stem = std::path::Path::new(program)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match stem.as_str() {
+        "vim" | "vi" | "nvim" | "gvim" | "view" => EditorKind::Vim,
+        "nano" | "pico" => EditorKind::Nano,
+        "emacs" | "emacsclient" => EditorKind::Emacs,
+        "hx" | "helix" => EditorKind::Helix,
+        "code" | "code-insiders" | "codium" | "vscodium" => EditorKind::VsCode,
+        _ => EditorKind::Other,
+    }
+}
+
+/// Compute the 1-based line and column of the caret placed at the end of
+/// `initial_text`, so the editor opens at the composer's current cursor.
+fn end_of_text_position(initial_text: &str) -> (usize, usize) {
+    let line = initial_text.bytes().filter(|&b| b == b'\n').count() + 1;
+    let last_line = initial_text.rsplit('\n').next().unwrap_or_default();
+    let col = last_line.chars().count() + 1;
+    (line, col)
+}
+
 fn join_command(command: &[String]) -> String {
     command.join(" ")
 }
@@ -1,10 +1,14 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
 use std::fs;
-use std::io::BufRead;
-use std::io::BufReader;
+use std::hash::Hasher;
+use std::io;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
 
 use chrono::Utc;
 use codex_core::rollout::path_utils::slug_for_cwd;
@@ -13,30 +17,320 @@ use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SessionMetaLine;
 use color_eyre::Result;
+use serde::Deserialize;
+use serde::Serialize;
 use serde_json::Value;
+use tempfile::NamedTempFile;
+use tempfile::TempPath;
 use tokio::task::spawn_blocking;
 
+/// Manifest filename, kept at the root of the `sessions` tree.
+const INDEX_FILE_NAME: &str = "index.jsonl";
+
+/// A structured summary of what normalization did (or, in dry-run mode, would
+/// do) to the sessions tree. Returned so a TUI can show a diff-like preview
+/// before touching the user's history, and an audit trail afterwards.
+#[derive(Debug, Default, Clone)]
+pub struct NormalizationReport {
+    /// Files detected as holding more than one cwd, with the line count routed
+    /// to each cwd key.
+    pub mixed_files: Vec<MixedFileReport>,
+    /// Files relocated (or to be relocated) from a bare day directory into a
+    /// per-cwd slug directory. In dry-run mode this only covers files that are
+    /// already single-cwd: a mixed file's children don't exist yet, so their
+    /// eventual per-cwd migrations aren't predicted here — see `mixed_files`
+    /// for those.
+    pub migrations: Vec<MigrationReport>,
+    /// Migrations whose intended target already existed, forcing a fresh
+    /// `ConversationId` in the filename.
+    pub collisions: Vec<CollisionReport>,
+    /// Lines that failed to parse as JSON, by source file. Blank lines are
+    /// skipped before parsing and never appear here, so an unchanged,
+    /// all-valid tree previews clean.
+    pub parse_failures: Vec<ParseFailure>,
+    /// Sidecar files holding lines that could not be parsed or decoded, written
+    /// verbatim so no bytes are lost when a file is rewritten.
+    pub quarantined: Vec<QuarantineReport>,
+}
+
+/// A single mixed-cwd file and the number of lines belonging to each cwd key.
+#[derive(Debug, Clone)]
+pub struct MixedFileReport {
+    pub path: PathBuf,
+    pub line_counts: BTreeMap<String, usize>,
+}
+
+/// A rollout file moved from `source` into its slug directory at `target`.
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    pub source: PathBuf,
+    pub target: PathBuf,
+}
+
+/// A migration whose `intended` target path was taken, resolved by minting a new
+/// `ConversationId` and writing to `resolved` instead.
+#[derive(Debug, Clone)]
+pub struct CollisionReport {
+    pub source: PathBuf,
+    pub intended: PathBuf,
+    pub resolved: PathBuf,
+}
+
+/// A line that could not be parsed as JSON while reading `path`.
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+    pub path: PathBuf,
+    pub line_number: usize,
+}
+
+/// A `rollout-<id>.corrupt.jsonl` sidecar holding `line_count` unrecoverable
+/// lines quarantined from the rewrite of a mixed-cwd file.
+#[derive(Debug, Clone)]
+pub struct QuarantineReport {
+    pub path: PathBuf,
+    pub line_count: usize,
+}
+
+/// A durable record of every output a mixed-file split is about to commit,
+/// written next to the original (as `<name>.split-plan.json`) before the
+/// rename loop in [`split_if_mixed`] begins. If a crash lands after every
+/// listed file is durably renamed but before the original is retired, the
+/// next run finds this plan, sees every file already in place, and finishes
+/// retiring the original instead of re-splitting it into fresh
+/// `ConversationId`s (which would duplicate every session in it).
+#[derive(Debug, Serialize, Deserialize)]
+struct SplitCommitPlan {
+    final_paths: Vec<PathBuf>,
+}
+
+/// A single manifest entry describing where a conversation's rollout lives and
+/// enough metadata to answer lookups — and to tell, without re-reading the
+/// file, whether the on-disk copy has changed since it was indexed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub conversation_id: ConversationId,
+    /// Path to the rollout file, relative to the `sessions` root so the manifest
+    /// stays valid if the tree is moved.
+    pub path: PathBuf,
+    /// Normalized cwd key (see [`normalize_cwd`]).
+    pub cwd: String,
+    /// Slug directory the rollout belongs to, as produced by [`slug_for_cwd`].
+    pub slug: String,
+    /// Timestamp of the first line, when present.
+    pub first_timestamp: Option<String>,
+    /// Number of non-empty lines in the file.
+    pub line_count: usize,
+    /// Cheap (non-cryptographic) digest of the file's bytes, recorded so
+    /// consumers can dedup or audit content changes. Staleness is decided by
+    /// size/mtime (see [`SessionIndex::is_fresh`]); this is identity, not a
+    /// freshness signal.
+    pub content_hash: String,
+    /// File size in bytes at index time.
+    pub size: u64,
+    /// File mtime in nanoseconds since the Unix epoch at index time.
+    pub mtime_ns: u128,
+}
+
+/// A lazily-refreshed manifest mapping [`ConversationId`] to the rollout file
+/// that holds it, so the TUI and resume flows can locate a session in O(1)
+/// without walking thousands of files. Normalization maintains it incrementally
+/// and consults it to skip files it has already split and migrated.
+#[derive(Debug, Default, Clone)]
+pub struct SessionIndex {
+    /// Absolute path to the `sessions` root the manifest is anchored at.
+    root: PathBuf,
+    /// Entries keyed by conversation id (stringified, since ids order/hash is
+    /// not relied upon here).
+    entries: HashMap<String, IndexEntry>,
+    /// Secondary index from relative rollout path to conversation id, so the
+    /// per-file lookups in the normalization walks stay O(1) rather than
+    /// scanning every entry.
+    by_path: HashMap<PathBuf, String>,
+    /// Set when an entry was added, removed, or refreshed and the manifest needs
+    /// to be written back.
+    dirty: bool,
+}
+
+impl SessionIndex {
+    /// Load the manifest rooted at `codex_home/sessions`. Missing or partially
+    /// corrupt manifests degrade to an empty/partial index rather than failing;
+    /// a stale entry is simply re-scanned on next use.
+    pub fn load(codex_home: &Path) -> Self {
+        let root = codex_home.join("sessions");
+        Self::load_at(&root)
+    }
+
+    fn load_at(root: &Path) -> Self {
+        let mut index = SessionIndex {
+            root: root.to_path_buf(),
+            ..Default::default()
+        };
+        let Ok(bytes) = fs::read(root.join(INDEX_FILE_NAME)) else {
+            return index;
+        };
+        for raw in split_jsonl_lines(&bytes) {
+            let Ok(line) = std::str::from_utf8(raw) else {
+                continue;
+            };
+            if let Ok(entry) = serde_json::from_str::<IndexEntry>(line) {
+                index.insert(entry);
+            }
+        }
+        index.dirty = false;
+        index
+    }
+
+    /// Resolve the absolute path of the rollout holding `id`, if the manifest
+    /// knows about it and the file still exists.
+    pub fn resolve_path_for_conversation(&self, id: &ConversationId) -> Option<PathBuf> {
+        let entry = self.entries.get(&id.to_string())?;
+        let path = self.root.join(&entry.path);
+        path.exists().then_some(path)
+    }
+
+    /// All known rollouts whose normalized cwd matches `cwd`.
+    pub fn find_sessions_by_cwd(&self, cwd: &str) -> Vec<IndexEntry> {
+        let key = normalize_cwd(cwd);
+        self.entries
+            .values()
+            .filter(|e| e.cwd == key)
+            .cloned()
+            .collect()
+    }
+
+    /// The recorded entry for `path`, if any (O(1) via the path index).
+    fn entry_for(&self, path: &Path) -> Option<&IndexEntry> {
+        let rel = self.rel(path)?;
+        let id = self.by_path.get(&rel)?;
+        self.entries.get(id)
+    }
+
+    /// True when `path` is recorded and its size/mtime still match — i.e. the
+    /// file is unchanged since it was indexed and can be trusted as already
+    /// normalized without re-parsing.
+    fn is_fresh(&self, path: &Path) -> bool {
+        let Some(entry) = self.entry_for(path) else {
+            return false;
+        };
+        match file_stat(path) {
+            Some((size, mtime_ns)) => entry.size == size && entry.mtime_ns == mtime_ns,
+            None => false,
+        }
+    }
+
+    /// Insert or replace an entry, keeping the path index in sync.
+    fn insert(&mut self, entry: IndexEntry) {
+        let id = entry.conversation_id.to_string();
+        self.by_path.insert(entry.path.clone(), id.clone());
+        self.entries.insert(id, entry);
+        self.dirty = true;
+    }
+
+    /// Scan `path` and record (or refresh) its entry. Files without a parseable
+    /// session meta carry no conversation id and are left unindexed. When the
+    /// file's bytes are already in hand, pass them as `bytes` to avoid a second
+    /// read.
+    fn upsert_file(&mut self, path: &Path, bytes: Option<&[u8]>) {
+        let Some(rel) = self.rel(path) else {
+            return;
+        };
+        if let Some(entry) = scan_index_entry(path, rel, bytes) {
+            self.insert(entry);
+        }
+    }
+
+    /// Drop the entry for `path` (e.g. a mixed file retired to `.mixed.bak`).
+    fn forget(&mut self, path: &Path) {
+        let Some(rel) = self.rel(path) else {
+            return;
+        };
+        if let Some(id) = self.by_path.remove(&rel) {
+            self.entries.remove(&id);
+            self.dirty = true;
+        }
+    }
+
+    /// Persist the manifest atomically when it has changed.
+    fn persist(&self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if !self.root.exists() {
+            return Ok(());
+        }
+        let temp = NamedTempFile::new_in(&self.root)?;
+        {
+            let mut fh = temp.as_file();
+            // Sort by path so the manifest has a stable, diffable order.
+            let mut entries: Vec<&IndexEntry> = self.entries.values().collect();
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+            for entry in entries {
+                writeln!(fh, "{}", serde_json::to_string(entry)?)?;
+            }
+            fh.flush()?;
+            fh.sync_all()?;
+        }
+        temp.into_temp_path().persist(self.root.join(INDEX_FILE_NAME))?;
+        Ok(())
+    }
+
+    fn rel(&self, path: &Path) -> Option<PathBuf> {
+        path.strip_prefix(&self.root).ok().map(Path::to_path_buf)
+    }
+}
+
+/// Walk the sessions tree and report what normalization would do, without
+/// touching the filesystem.
+pub async fn normalize_sessions_plan(codex_home: &Path) -> Result<NormalizationReport> {
+    normalize_sessions(codex_home, true).await
+}
+
 /// Ensure every rollout file belongs to a single cwd.
 /// If a file contains messages from multiple cwds, split it into separate files,
 /// one per cwd, preserving timestamps and data. The original file is kept with
 /// a `.mixed.bak` suffix to avoid data loss.
-pub async fn normalize_sessions(codex_home: &Path) -> Result<()> {
+///
+/// When `dry_run` is set the tree is only inspected and the returned
+/// [`NormalizationReport`] describes the pending work; otherwise the work is
+/// performed and the same report describes what was done.
+pub async fn normalize_sessions(codex_home: &Path, dry_run: bool) -> Result<NormalizationReport> {
     let root = codex_home.join("sessions");
     if !root.exists() {
-        return Ok(());
+        return Ok(NormalizationReport::default());
     }
     let root = root.canonicalize().unwrap_or(root);
-    spawn_blocking(move || normalize_sync(&root)).await??;
-    Ok(())
+    let report = spawn_blocking(move || normalize_sync(&root, dry_run)).await??;
+    Ok(report)
 }
 
-fn normalize_sync(root: &Path) -> Result<()> {
-    split_mixed_cwds(root)?;
-    migrate_into_slug_dirs(root)?;
-    Ok(())
+fn normalize_sync(root: &Path, dry_run: bool) -> Result<NormalizationReport> {
+    let mut report = NormalizationReport::default();
+    let mut index = SessionIndex::load_at(root);
+    split_mixed_cwds(root, dry_run, &mut report, &mut index)?;
+    // In a real run every mixed file is already renamed to `.mixed.bak` (and
+    // thus no longer has a `.jsonl` extension) by the time this walk starts, so
+    // it's naturally skipped. In dry-run the mixed original is still sitting
+    // there as a `.jsonl` file; skip it explicitly so it doesn't produce a
+    // phantom single-cwd `MigrationReport` for what the real run will instead
+    // split into several per-cwd children (see `mixed_files`).
+    let mixed_paths: HashSet<PathBuf> = if dry_run {
+        report.mixed_files.iter().map(|m| m.path.clone()).collect()
+    } else {
+        HashSet::new()
+    };
+    migrate_into_slug_dirs(root, dry_run, &mut report, &mut index, &mixed_paths)?;
+    if !dry_run {
+        index.persist()?;
+    }
+    Ok(report)
 }
 
-fn split_mixed_cwds(root: &Path) -> Result<()> {
+fn split_mixed_cwds(
+    root: &Path,
+    dry_run: bool,
+    report: &mut NormalizationReport,
+    index: &mut SessionIndex,
+) -> Result<()> {
     let mut stack = vec![root.to_path_buf()];
     while let Some(dir) = stack.pop() {
         let Ok(read_dir) = fs::read_dir(&dir) else {
@@ -55,13 +349,27 @@ fn split_mixed_cwds(root: &Path) -> Result<()> {
             {
                 continue;
             }
-            split_if_mixed(&path)?;
+            if is_normalizer_artifact(&path) {
+                continue;
+            }
+            // A file unchanged since it was indexed is already known to hold a
+            // single cwd; trust the manifest and skip the re-parse.
+            if index.is_fresh(&path) {
+                continue;
+            }
+            split_if_mixed(&path, dry_run, report, index)?;
         }
     }
     Ok(())
 }
 
-fn migrate_into_slug_dirs(root: &Path) -> Result<()> {
+fn migrate_into_slug_dirs(
+    root: &Path,
+    dry_run: bool,
+    report: &mut NormalizationReport,
+    index: &mut SessionIndex,
+    mixed_paths: &HashSet<PathBuf>,
+) -> Result<()> {
     let mut stack = vec![root.to_path_buf()];
     while let Some(dir) = stack.pop() {
         let Ok(read_dir) = fs::read_dir(&dir) else {
@@ -80,25 +388,78 @@ fn migrate_into_slug_dirs(root: &Path) -> Result<()> {
             {
                 continue;
             }
-            migrate_file_if_needed(root, &path)?;
+            if is_normalizer_artifact(&path) {
+                continue;
+            }
+            if mixed_paths.contains(&path) {
+                continue;
+            }
+            migrate_file_if_needed(root, &path, dry_run, report, index)?;
         }
     }
     Ok(())
 }
 
-fn split_if_mixed(path: &Path) -> Result<()> {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
+fn split_if_mixed(
+    path: &Path,
+    dry_run: bool,
+    report: &mut NormalizationReport,
+    index: &mut SessionIndex,
+) -> Result<()> {
+    let bytes = match fs::read(path) {
+        Ok(b) => b,
         Err(_) => return Ok(()),
     };
-    let reader = BufReader::new(file);
+
+    let plan_path = split_plan_path(path);
+    if !dry_run && let Some(plan) = read_split_commit_plan(&plan_path) {
+        if plan.final_paths.iter().all(|p| p.exists()) {
+            // A previous run durably renamed every split output before it was
+            // interrupted; finish retiring the original under the ids already
+            // committed rather than re-splitting it from scratch.
+            retire_mixed_original(path, &plan.final_paths, index)?;
+            let _ = fs::remove_file(&plan_path);
+            return Ok(());
+        }
+        // Partial or unrecoverable: there's no way to finish that particular
+        // split. The original still holds every line, so remove whichever of
+        // its outputs were already renamed into place, then fall through and
+        // start a fresh split.
+        for output in &plan.final_paths {
+            let _ = fs::remove_file(output);
+            index.forget(output);
+        }
+        let _ = fs::remove_file(&plan_path);
+    }
+
     let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+    // Lines that are not valid UTF-8 or not valid JSON are kept verbatim, along
+    // with their original line terminator, in the quarantine sidecar rather
+    // than silently vanishing during the rewrite. Valid lines are re-serialized
+    // (and their session meta re-stamped) into the per-cwd outputs, so the
+    // outputs plus the sidecar are not a byte-for-byte copy of the input; the
+    // original bytes survive only in the `.mixed.bak` backup.
+    let mut quarantine: Vec<(Vec<u8>, &'static [u8])> = Vec::new();
     let mut current_cwd: Option<String> = None;
     let mut first_ts: Option<String> = None;
 
-    for line in reader.lines().map_while(Result::ok) {
-        let Ok(mut val) = serde_json::from_str::<Value>(&line) else {
-            continue;
+    for (idx, (raw, terminator)) in split_jsonl_lines_with_terminators(&bytes)
+        .into_iter()
+        .enumerate()
+    {
+        // Blank separator lines are not corruption; skip them before
+        // attempting to parse, matching `read_cwd` and `scan_index_entry`.
+        let mut val = match classify_line(raw) {
+            ClassifiedLine::Blank => continue,
+            ClassifiedLine::Value(v) => v,
+            ClassifiedLine::Unparseable => {
+                report.parse_failures.push(ParseFailure {
+                    path: path.to_path_buf(),
+                    line_number: idx + 1,
+                });
+                quarantine.push((raw.to_vec(), terminator));
+                continue;
+            }
         };
         if first_ts.is_none() {
             first_ts = val
@@ -123,6 +484,23 @@ fn split_if_mixed(path: &Path) -> Result<()> {
     }
 
     if groups.len() <= 1 {
+        // Single-cwd file: nothing to split, but record it so future runs can
+        // skip the re-parse entirely. The bytes are already in hand.
+        if !dry_run {
+            index.upsert_file(path, Some(&bytes));
+        }
+        return Ok(());
+    }
+
+    report.mixed_files.push(MixedFileReport {
+        path: path.to_path_buf(),
+        line_counts: groups
+            .iter()
+            .map(|(k, v)| (k.clone(), v.len()))
+            .collect(),
+    });
+
+    if dry_run {
         return Ok(());
     }
 
@@ -130,6 +508,16 @@ fn split_if_mixed(path: &Path) -> Result<()> {
         .or(first_ts)
         .unwrap_or_else(|| Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string());
 
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+
+    // Stage every split output as a temp file in the target directory, fsync it,
+    // then rename it into place. Only once *all* outputs are durably renamed do we
+    // rename the original to its backup, so a crash mid-rewrite never loses data
+    // and a reader never observes a half-written rollout file. On a failure before
+    // any rename starts, the temp files are cleaned up on drop and the original is
+    // left untouched; a crash partway through the rename loop is instead recovered
+    // via the `SplitCommitPlan` written just below.
+    let mut staged: Vec<(TempPath, PathBuf)> = Vec::with_capacity(groups.len());
     for (cwd_key, mut items) in groups {
         let new_id = ConversationId::new();
         for val in items.iter_mut() {
@@ -140,23 +528,113 @@ fn split_if_mixed(path: &Path) -> Result<()> {
             }
         }
         let file_name = format!("rollout-{ts_segment}-{new_id}.jsonl");
-        let new_path = path
-            .parent()
-            .unwrap_or_else(|| Path::new("."))
-            .join(file_name);
-        let mut fh = fs::File::create(&new_path)?;
-        for v in items {
-            writeln!(fh, "{}", serde_json::to_string(&v)?)?;
+        let final_path = parent.join(file_name);
+
+        let temp = NamedTempFile::new_in(parent)?;
+        {
+            let mut fh = temp.as_file();
+            for v in items {
+                writeln!(fh, "{}", serde_json::to_string(&v)?)?;
+            }
+            fh.flush()?;
+            fh.sync_all()?;
+        }
+        staged.push((temp.into_temp_path(), final_path));
+    }
+
+    // Route any unrecoverable lines to a sidecar alongside the outputs, staged
+    // with the same durability discipline.
+    if !quarantine.is_empty() {
+        let corrupt_id = ConversationId::new();
+        let file_name = format!("rollout-{ts_segment}-{corrupt_id}.corrupt.jsonl");
+        let final_path = parent.join(file_name);
+        let temp = NamedTempFile::new_in(parent)?;
+        {
+            let mut fh = temp.as_file();
+            for (raw, terminator) in &quarantine {
+                fh.write_all(raw)?;
+                fh.write_all(terminator)?;
+            }
+            fh.flush()?;
+            fh.sync_all()?;
+        }
+        staged.push((temp.into_temp_path(), final_path.clone()));
+        report.quarantined.push(QuarantineReport {
+            path: final_path,
+            line_count: quarantine.len(),
+        });
+    }
+
+    // Persist a commit record of every final path before starting the rename
+    // loop. The loop itself can't be made atomic (it's N renames, not one), so
+    // this record is what makes a crash partway through recoverable: if a
+    // later run finds every listed file already in place, it resumes from
+    // "retire the original" instead of minting a fresh set of ids.
+    {
+        let plan = SplitCommitPlan {
+            final_paths: staged.iter().map(|(_, final_path)| final_path.clone()).collect(),
+        };
+        let temp = NamedTempFile::new_in(parent)?;
+        {
+            let mut fh = temp.as_file();
+            fh.write_all(&serde_json::to_vec(&plan)?)?;
+            fh.flush()?;
+            fh.sync_all()?;
         }
+        temp.into_temp_path().persist(&plan_path)?;
     }
 
-    // keep original as backup
+    let mut persisted: Vec<PathBuf> = Vec::with_capacity(staged.len());
+    for (temp_path, final_path) in staged {
+        // `persist` renames atomically within the directory and, on error, hands
+        // back the temp file so the remaining staged outputs are cleaned up on drop.
+        temp_path.persist(&final_path)?;
+        persisted.push(final_path);
+    }
+
+    // All split outputs are durable; now retire the original as a backup. If
+    // this fails, the mixed original must NOT be treated as handled: bail out
+    // before touching the index so the next run still sees it and re-splits it
+    // instead of silently duplicating every session in it on each retry.
+    retire_mixed_original(path, &persisted, index)?;
+    let _ = fs::remove_file(&plan_path);
+    Ok(())
+}
+
+/// Rename the mixed `path` to its `.mixed.bak` backup and move its manifest
+/// entry over to `outputs`, the single-cwd files that replace it. Shared by
+/// the normal completion path and by the crash-recovery path that resumes
+/// from a [`SplitCommitPlan`] left by an interrupted run.
+fn retire_mixed_original(path: &Path, outputs: &[PathBuf], index: &mut SessionIndex) -> Result<()> {
     let backup = path.with_extension("mixed.bak");
-    let _ = fs::rename(path, backup);
+    fs::rename(path, backup)?;
+    index.forget(path);
+    for output in outputs {
+        index.upsert_file(output, None);
+    }
     Ok(())
 }
 
-fn migrate_file_if_needed(root: &Path, path: &Path) -> Result<()> {
+/// Path of the [`SplitCommitPlan`] sidecar for a mixed rollout at `path`.
+/// `.json` (not `.jsonl`) so the directory walks' extension filter already
+/// excludes it without needing to extend [`is_normalizer_artifact`].
+fn split_plan_path(path: &Path) -> PathBuf {
+    path.with_extension("split-plan.json")
+}
+
+/// Read and parse a leftover [`SplitCommitPlan`], if any.
+fn read_split_commit_plan(plan_path: &Path) -> Option<SplitCommitPlan> {
+    let bytes = fs::read(plan_path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn migrate_file_if_needed(
+    root: &Path,
+    path: &Path,
+    dry_run: bool,
+    report: &mut NormalizationReport,
+    index: &mut SessionIndex,
+) -> Result<()> {
     let rel = match path.strip_prefix(root) {
         Ok(r) => r,
         Err(_) => return Ok(()),
@@ -179,10 +657,17 @@ fn migrate_file_if_needed(root: &Path, path: &Path) -> Result<()> {
         return Ok(());
     }
 
-    let Some(cwd) = read_cwd(path) else {
-        return Ok(());
+    // Reuse the slug cached in the manifest when the file is unchanged, avoiding
+    // a re-read of the session meta; otherwise scan the file directly. Reusing
+    // the stored slug (rather than the stored cwd) keeps warm and cold runs on
+    // the exact same target directory.
+    let slug = match index.is_fresh(path).then(|| index.entry_for(path)).flatten() {
+        Some(entry) => entry.slug.clone(),
+        None => match read_cwd(path) {
+            Some(cwd) => slug_for_cwd(&cwd),
+            None => return Ok(()),
+        },
     };
-    let slug = slug_for_cwd(&cwd);
 
     let mut target_dir = root.to_path_buf();
     // comps: [year, month, day, file]
@@ -190,13 +675,13 @@ fn migrate_file_if_needed(root: &Path, path: &Path) -> Result<()> {
     target_dir.push(month.as_os_str());
     target_dir.push(day.as_os_str());
     target_dir.push(slug);
-    fs::create_dir_all(&target_dir)?;
 
     let filename = path
         .file_name()
         .map(std::ffi::OsStr::to_os_string)
         .unwrap_or_default();
-    let mut target_path = target_dir.join(&filename);
+    let intended = target_dir.join(&filename);
+    let mut target_path = intended.clone();
     if target_path.exists() {
         // Avoid collision: regenerate id
         if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
@@ -204,11 +689,122 @@ fn migrate_file_if_needed(root: &Path, path: &Path) -> Result<()> {
             let new_name = format!("{stem}-{new_id}.jsonl");
             target_path = target_dir.join(new_name);
         }
+        report.collisions.push(CollisionReport {
+            source: path.to_path_buf(),
+            intended,
+            resolved: target_path.clone(),
+        });
     }
-    fs::rename(path, target_path)?;
+
+    report.migrations.push(MigrationReport {
+        source: path.to_path_buf(),
+        target: target_path.clone(),
+    });
+
+    if dry_run {
+        return Ok(());
+    }
+
+    fs::create_dir_all(&target_dir)?;
+
+    // Stage the migrated copy in the target directory, fsync it, then rename into
+    // place so the move is atomic even across filesystems; only drop the source
+    // once the destination is durable.
+    let mut temp = NamedTempFile::new_in(&target_dir)?;
+    {
+        let mut src = fs::File::open(path)?;
+        io::copy(&mut src, temp.as_file_mut())?;
+        let fh = temp.as_file();
+        fh.flush()?;
+        fh.sync_all()?;
+    }
+    temp.into_temp_path().persist(&target_path)?;
+    fs::remove_file(path)?;
+
+    // The rollout now lives at its slug path; move its manifest entry with it.
+    index.forget(path);
+    index.upsert_file(&target_path, None);
     Ok(())
 }
 
+/// Split raw file bytes into newline-delimited lines, mirroring
+/// [`std::io::BufRead::lines`]: each `\n` terminates a line, a trailing `\r` is
+/// trimmed, and a final empty segment from a trailing newline is dropped. Unlike
+/// `lines()` this keeps non-UTF-8 bytes instead of aborting at the first one.
+fn split_jsonl_lines(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut lines: Vec<&[u8]> = bytes
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .collect();
+    if bytes.ends_with(b"\n") {
+        lines.pop();
+    }
+    lines
+}
+
+/// Like [`split_jsonl_lines`] but also yields each line's original terminator
+/// (`"\r\n"`, `"\n"`, or empty for a final line with none). Used by
+/// `split_if_mixed` so quarantined bytes are written back with their exact
+/// original terminator instead of always gaining a trailing `"\n"`, which
+/// would turn a CRLF line into LF or hand a missing final newline to a line
+/// that never had one.
+fn split_jsonl_lines_with_terminators(bytes: &[u8]) -> Vec<(&[u8], &'static [u8])> {
+    let mut lines = Vec::new();
+    let mut rest = bytes;
+    while let Some(pos) = rest.iter().position(|&b| b == b'\n') {
+        let line = &rest[..pos];
+        match line.strip_suffix(b"\r") {
+            Some(content) => lines.push((content, b"\r\n".as_slice())),
+            None => lines.push((line, b"\n".as_slice())),
+        }
+        rest = &rest[pos + 1..];
+    }
+    if !rest.is_empty() {
+        lines.push((rest, b"".as_slice()));
+    }
+    lines
+}
+
+/// What a raw JSONL line turned out to be when `split_if_mixed` looked at it.
+#[derive(Debug, PartialEq)]
+enum ClassifiedLine {
+    /// A blank or whitespace-only separator line; not corruption, simply
+    /// skipped.
+    Blank,
+    /// Valid JSON, ready to be routed into its cwd group.
+    Value(Value),
+    /// Not valid UTF-8 or not valid JSON; must be quarantined verbatim.
+    Unparseable,
+}
+
+/// Classify a raw line from [`split_jsonl_lines_with_terminators`] for the
+/// mixed-cwd split: blank, parsed JSON, or unparseable (and thus bound for
+/// quarantine). Pulled out of `split_if_mixed` so the blank/quarantine
+/// decision is unit-testable on its own.
+fn classify_line(raw: &[u8]) -> ClassifiedLine {
+    let Ok(s) = std::str::from_utf8(raw) else {
+        return ClassifiedLine::Unparseable;
+    };
+    if s.trim().is_empty() {
+        return ClassifiedLine::Blank;
+    }
+    match serde_json::from_str::<Value>(s) {
+        Ok(v) => ClassifiedLine::Value(v),
+        Err(_) => ClassifiedLine::Unparseable,
+    }
+}
+
+/// Files the normalizer itself produces and must not treat as rollouts: the
+/// index manifest and the `rollout-<id>.corrupt.jsonl` quarantine sidecars.
+/// Walking back over them would re-report their lines and, for sidecars, scan
+/// them fruitlessly on every run since they carry no session meta.
+fn is_normalizer_artifact(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    name == INDEX_FILE_NAME || name.ends_with(".corrupt.jsonl")
+}
+
 fn normalize_cwd(cwd: &str) -> String {
     cwd.replace('\\', "/")
         .trim_start_matches("//?/")
@@ -225,16 +821,20 @@ fn timestamp_segment_from_filename(path: &Path) -> Option<String> {
 }
 
 fn read_cwd(path: &Path) -> Option<std::path::PathBuf> {
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
-    for line in reader.lines().map_while(Result::ok) {
+    let bytes = fs::read(path).ok()?;
+    // Read raw and decode per line so a single non-UTF-8 line doesn't stop the
+    // scan before the session meta is found.
+    for raw in split_jsonl_lines(&bytes) {
+        let Ok(line) = std::str::from_utf8(raw) else {
+            continue;
+        };
         if line.trim().is_empty() {
             continue;
         }
-        if let Ok(meta) = serde_json::from_str::<SessionMetaLine>(&line) {
+        if let Ok(meta) = serde_json::from_str::<SessionMetaLine>(line) {
             return Some(meta.meta.cwd);
         }
-        if let Ok(rollout) = serde_json::from_str::<RolloutLine>(&line) {
+        if let Ok(rollout) = serde_json::from_str::<RolloutLine>(line) {
             match rollout.item {
                 RolloutItem::SessionMeta(session) => return Some(session.meta.cwd),
                 RolloutItem::TurnContext(tc) => return Some(tc.cwd),
@@ -244,3 +844,225 @@ fn read_cwd(path: &Path) -> Option<std::path::PathBuf> {
     }
     None
 }
+
+/// File size and mtime (nanoseconds since the Unix epoch), the pair used to
+/// decide whether an indexed entry is still fresh.
+fn file_stat(path: &Path) -> Option<(u64, u128)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime_ns = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    Some((meta.len(), mtime_ns))
+}
+
+/// Cheap, dependency-free content digest of a file's bytes, rendered as hex.
+/// Not cryptographic; it is recorded in the manifest only and is not consulted
+/// when deciding freshness, so edits that preserve size and mtime go unnoticed.
+fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(bytes);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Scan a rollout file into an [`IndexEntry`]. Returns `None` when the file
+/// can't be read or carries no parseable session meta (and thus no id). Pass
+/// `bytes` when the caller already holds the file contents to skip a re-read.
+fn scan_index_entry(path: &Path, rel: PathBuf, bytes: Option<&[u8]>) -> Option<IndexEntry> {
+    let owned;
+    let bytes = match bytes {
+        Some(b) => b,
+        None => {
+            owned = fs::read(path).ok()?;
+            &owned
+        }
+    };
+    let (size, mtime_ns) = file_stat(path)?;
+
+    let mut id: Option<ConversationId> = None;
+    let mut cwd: Option<PathBuf> = None;
+    let mut first_timestamp: Option<String> = None;
+    let mut line_count = 0usize;
+
+    for raw in split_jsonl_lines(bytes) {
+        let Ok(line) = std::str::from_utf8(raw) else {
+            continue;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        line_count += 1;
+        if first_timestamp.is_none()
+            && let Ok(val) = serde_json::from_str::<Value>(line)
+        {
+            first_timestamp = val
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .map(ToString::to_string);
+        }
+        if id.is_some() {
+            continue;
+        }
+        if let Ok(meta) = serde_json::from_str::<SessionMetaLine>(line) {
+            id = Some(meta.meta.id);
+            cwd = Some(meta.meta.cwd);
+        } else if let Ok(rollout) = serde_json::from_str::<RolloutLine>(line) {
+            if let RolloutItem::SessionMeta(session) = &rollout.item {
+                id = Some(session.meta.id);
+                cwd = Some(session.meta.cwd.clone());
+            }
+        }
+    }
+
+    let conversation_id = id?;
+    let raw_cwd = cwd.unwrap_or_default();
+    // Slug is derived from the raw cwd so it matches the cold-path computation
+    // in `migrate_file_if_needed`; the stored `cwd` key is normalized for lookup.
+    let slug = slug_for_cwd(&raw_cwd);
+    let cwd = normalize_cwd(raw_cwd.to_str().unwrap_or_default());
+    Some(IndexEntry {
+        conversation_id,
+        path: rel,
+        cwd,
+        slug,
+        first_timestamp,
+        line_count,
+        content_hash: content_hash(bytes),
+        size,
+        mtime_ns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terminators_round_trip_crlf_lf_and_missing_final_newline() {
+        let bytes = b"one\r\ntwo\nthree";
+        let lines = split_jsonl_lines_with_terminators(bytes);
+        assert_eq!(
+            lines,
+            vec![
+                (b"one".as_slice(), b"\r\n".as_slice()),
+                (b"two".as_slice(), b"\n".as_slice()),
+                (b"three".as_slice(), b"".as_slice()),
+            ]
+        );
+        // Reassembling content + terminator must reproduce the input exactly.
+        let rebuilt: Vec<u8> = lines
+            .iter()
+            .flat_map(|(content, terminator)| content.iter().chain(terminator.iter()).copied())
+            .collect();
+        assert_eq!(rebuilt, bytes);
+    }
+
+    #[test]
+    fn terminators_empty_input_yields_no_lines() {
+        assert!(split_jsonl_lines_with_terminators(b"").is_empty());
+    }
+
+    #[test]
+    fn terminators_trailing_newline_drops_no_final_empty_segment() {
+        let lines = split_jsonl_lines_with_terminators(b"only\n");
+        assert_eq!(lines, vec![(b"only".as_slice(), b"\n".as_slice())]);
+    }
+
+    #[test]
+    fn classify_line_skips_blank_and_whitespace_only_lines() {
+        assert_eq!(classify_line(b""), ClassifiedLine::Blank);
+        assert_eq!(classify_line(b"   "), ClassifiedLine::Blank);
+        assert_eq!(classify_line(b"\t"), ClassifiedLine::Blank);
+    }
+
+    #[test]
+    fn classify_line_parses_valid_json() {
+        assert_eq!(
+            classify_line(br#"{"a":1}"#),
+            ClassifiedLine::Value(serde_json::json!({"a": 1}))
+        );
+    }
+
+    #[test]
+    fn classify_line_flags_invalid_json_and_non_utf8_as_unparseable() {
+        assert_eq!(classify_line(b"{not json"), ClassifiedLine::Unparseable);
+        assert_eq!(classify_line(&[0xff, 0xfe]), ClassifiedLine::Unparseable);
+    }
+
+    #[test]
+    fn split_commit_plan_round_trips_through_json() {
+        let plan = SplitCommitPlan {
+            final_paths: vec![PathBuf::from("a.jsonl"), PathBuf::from("b.jsonl")],
+        };
+        let bytes = serde_json::to_vec(&plan).expect("serialize plan");
+        let parsed: SplitCommitPlan = serde_json::from_slice(&bytes).expect("parse plan");
+        assert_eq!(parsed.final_paths, plan.final_paths);
+    }
+
+    #[test]
+    fn split_plan_path_uses_split_plan_json_extension() {
+        let path = Path::new("/sessions/2024/01/01/rollout-2024-01-01T00-00-00-abc.jsonl");
+        assert_eq!(
+            split_plan_path(path),
+            Path::new("/sessions/2024/01/01/rollout-2024-01-01T00-00-00-abc.split-plan.json")
+        );
+    }
+
+    #[test]
+    fn resume_from_leftover_plan_retires_original_without_minting_new_ids() {
+        // Simulates the crash window the fix targets: every split output was
+        // already durably renamed into place, but the process died before the
+        // mixed original was retired. The next run must find the leftover
+        // plan, see every listed file present, and finish retiring the
+        // original under the already-committed outputs instead of re-splitting
+        // it into a fresh set of ids.
+        let dir = tempfile::tempdir().expect("tempdir");
+        let original = dir.path().join("rollout-2024-01-01T00-00-00-orig.jsonl");
+        fs::write(&original, b"{\"mixed\":true}\n").expect("write original");
+
+        let child_a = dir.path().join("rollout-2024-01-01T00-00-00-aaa.jsonl");
+        let child_b = dir.path().join("rollout-2024-01-01T00-00-00-bbb.jsonl");
+        fs::write(&child_a, b"{\"a\":1}\n").expect("write child a");
+        fs::write(&child_b, b"{\"b\":1}\n").expect("write child b");
+
+        let plan_path = split_plan_path(&original);
+        let plan = SplitCommitPlan {
+            final_paths: vec![child_a.clone(), child_b.clone()],
+        };
+        fs::write(&plan_path, serde_json::to_vec(&plan).unwrap()).expect("write plan");
+
+        let loaded = read_split_commit_plan(&plan_path).expect("plan should parse");
+        assert!(loaded.final_paths.iter().all(|p| p.exists()));
+
+        let mut index = SessionIndex::load_at(dir.path());
+        retire_mixed_original(&original, &loaded.final_paths, &mut index).expect("retire");
+
+        assert!(!original.exists());
+        assert!(original.with_extension("mixed.bak").exists());
+        assert!(child_a.exists());
+        assert!(child_b.exists());
+    }
+
+    #[test]
+    fn quarantine_preserves_original_terminator_per_line() {
+        // A CRLF-terminated corrupt line and a final corrupt line with no
+        // trailing newline must each keep their own terminator rather than
+        // both being normalized to "\n", which would misrepresent the
+        // original bytes in the .corrupt.jsonl sidecar.
+        let bytes = b"{not json\r\nalso not json";
+        let mut quarantine: Vec<(Vec<u8>, &'static [u8])> = Vec::new();
+        for (raw, terminator) in split_jsonl_lines_with_terminators(bytes) {
+            if let ClassifiedLine::Unparseable = classify_line(raw) {
+                quarantine.push((raw.to_vec(), terminator));
+            }
+        }
+        let mut rebuilt = Vec::new();
+        for (raw, terminator) in &quarantine {
+            rebuilt.extend_from_slice(raw);
+            rebuilt.extend_from_slice(terminator);
+        }
+        assert_eq!(rebuilt, bytes);
+    }
+}